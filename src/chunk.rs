@@ -1,28 +1,118 @@
 use crate::mesh::MeshVertex;
 use crate::worker::pool::Pool;
 use crate::{ecs::component::*, worker::worker::Worker};
+use cgmath::InnerSpace;
 use legion::World;
 use noise::Fbm;
 use noise::{
     utils::{NoiseMapBuilder, PlaneMapBuilder},
     NoiseFn,
 };
+use std::cell::RefCell;
 use std::thread;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{mpsc, Arc, Mutex},
 };
 
+type ChunkLocation = (i32, i32);
+
+fn location_key(chunk_location: cgmath::Vector2<f32>) -> ChunkLocation {
+    (chunk_location.x as i32, chunk_location.y as i32)
+}
+
+const DEFAULT_MAX_IN_FLIGHT: usize = 4;
+const DEFAULT_MAX_COMPLETIONS_PER_UPDATE: usize = 1;
+
+struct Candidate {
+    position: cgmath::Vector3<f32>,
+    chunk_location: cgmath::Vector2<f32>,
+    location: ChunkLocation,
+}
+
+/// Sorts farthest-first, so `pump` can pop the nearest candidate off the end.
+fn sort_candidates_farthest_first(queue: &mut [Candidate], camera_position: cgmath::Vector3<f32>) {
+    queue.sort_by(|a, b| {
+        let a_dist = (a.position - camera_position).magnitude2();
+        let b_dist = (b.position - camera_position).magnitude2();
+        b_dist
+            .partial_cmp(&a_dist)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// Which chunk locations are currently building, plus any rebuild deferred
+/// for a location already in flight. Split out of `ChunkManager` so it's
+/// testable without a `wgpu::Device`.
+#[derive(Default)]
+struct BuildTracker {
+    building: HashSet<ChunkLocation>,
+    dirty: HashMap<ChunkLocation, (cgmath::Vector3<f32>, cgmath::Vector2<f32>)>,
+}
+
+impl BuildTracker {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_building(&self, location: ChunkLocation) -> bool {
+        self.building.contains(&location)
+    }
+
+    fn in_flight(&self) -> usize {
+        self.building.len()
+    }
+
+    fn set_building(&mut self, location: ChunkLocation) {
+        self.building.insert(location);
+    }
+
+    fn reset_building(&mut self, location: ChunkLocation) {
+        self.building.remove(&location);
+    }
+
+    /// Returns `true` if `location` should be dispatched right away, `false`
+    /// if it's already building and the rebuild was stashed for later.
+    fn mark_dirty(
+        &mut self,
+        location: ChunkLocation,
+        position: cgmath::Vector3<f32>,
+        chunk_location: cgmath::Vector2<f32>,
+    ) -> bool {
+        if self.is_building(location) {
+            self.dirty.insert(location, (position, chunk_location));
+            false
+        } else {
+            true
+        }
+    }
+
+    fn take_dirty(
+        &mut self,
+        location: ChunkLocation,
+    ) -> Option<(cgmath::Vector3<f32>, cgmath::Vector2<f32>)> {
+        self.dirty.remove(&location)
+    }
+}
+
 pub struct ChunkManager {
     current_idx: u32,
     receiver: mpsc::Receiver<MeshReference>,
     sender: mpsc::Sender<MeshReference>,
+    buffer_return: mpsc::Sender<FreeBuffers>,
     pool: Pool<ChunkWork, ChunkWorkerInitializer, ChunkWorker>,
     pending: HashMap<u32, PendingWork>,
+    voxel_world: VoxelWorld,
+    tracker: BuildTracker,
+    queue: Vec<Candidate>,
+    queued: HashSet<ChunkLocation>,
+    max_in_flight: usize,
+    max_completions_per_update: usize,
 }
 
 struct PendingWork {
     position: cgmath::Vector3<f32>,
+    location: ChunkLocation,
     killer: mpsc::Sender<bool>,
 }
 
@@ -35,33 +125,103 @@ impl PendingWork {
 impl ChunkManager {
     pub fn new(device: Arc<wgpu::Device>) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let (buffer_return, buffer_return_receiver) = mpsc::channel();
         Self {
             current_idx: 1,
             receiver,
             sender,
+            buffer_return,
             pool: Pool::new(
                 1,
                 ChunkWorkerInitializer {
                     device: Arc::clone(&device),
+                    buffer_return: Arc::new(Mutex::new(buffer_return_receiver)),
+                    registry: Arc::new(BlockRegistry::new()),
                 },
             ),
             pending: HashMap::new(),
+            voxel_world: VoxelWorld::new(),
+            tracker: BuildTracker::new(),
+            queue: Vec::new(),
+            queued: HashSet::new(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            max_completions_per_update: DEFAULT_MAX_COMPLETIONS_PER_UPDATE,
         }
     }
 
+    pub fn set_max_in_flight(&mut self, max_in_flight: usize) {
+        self.max_in_flight = max_in_flight;
+    }
+
+    pub fn set_max_completions_per_update(&mut self, max_completions_per_update: usize) {
+        self.max_completions_per_update = max_completions_per_update;
+    }
+
+    /// Returns a completed mesh's buffers to the pool's free list for reuse.
+    pub fn recycle(
+        &self,
+        vertex_data: Box<[MeshVertex]>,
+        index_data: Box<[u32]>,
+        transparent_vertex_data: Box<[MeshVertex]>,
+        transparent_index_data: Box<[u32]>,
+    ) {
+        let mut buffers = FreeBuffers {
+            vertices: vertex_data.into_vec(),
+            indices: index_data.into_vec(),
+            transparent_vertices: transparent_vertex_data.into_vec(),
+            transparent_indices: transparent_index_data.into_vec(),
+        };
+        buffers.clear();
+        let _ = self.buffer_return.send(buffers);
+    }
+
+    /// Queues a build candidate, unless one is already queued or in flight
+    /// for `chunk_location`.
     pub fn dispatch(
         &mut self,
         position: cgmath::Vector3<f32>,
         chunk_location: cgmath::Vector2<f32>,
     ) {
+        let location = location_key(chunk_location);
+        if self.tracker.is_building(location) || !self.queued.insert(location) {
+            return;
+        }
+        self.queue.push(Candidate {
+            position,
+            chunk_location,
+            location,
+        });
+    }
+
+    /// Submits queued candidates nearest `camera_position` first, up to
+    /// `max_in_flight` builds at once.
+    fn pump(&mut self, camera_position: cgmath::Vector3<f32>) {
+        sort_candidates_farthest_first(&mut self.queue, camera_position);
+
+        while self.tracker.in_flight() < self.max_in_flight {
+            let candidate = match self.queue.pop() {
+                Some(candidate) => candidate,
+                None => break,
+            };
+            self.queued.remove(&candidate.location);
+            self.submit(candidate, camera_position);
+        }
+    }
+
+    fn submit(&mut self, candidate: Candidate, camera_position: cgmath::Vector3<f32>) {
+        self.tracker.set_building(candidate.location);
+
         let (sender, receiver) = mpsc::channel();
         let pending_work = PendingWork {
-            position,
+            position: candidate.position,
+            location: candidate.location,
             killer: sender,
         };
         let work = ChunkWork {
             idx: self.current_idx,
-            position: chunk_location,
+            position: candidate.position,
+            camera_position,
+            snapshot: self.voxel_world.snapshot(candidate.chunk_location),
             receiver,
             sender: self.sender.clone(),
         };
@@ -70,9 +230,31 @@ impl ChunkManager {
         self.pool.dispatch(work);
     }
 
-    pub fn update(&mut self, world: &mut World) {
-        if let Ok(chunk) = self.receiver.try_recv() {
+    /// Queues a rebuild of `chunk_location`, deferred until any in-flight
+    /// build for it completes.
+    pub fn mark_dirty(
+        &mut self,
+        position: cgmath::Vector3<f32>,
+        chunk_location: cgmath::Vector2<f32>,
+    ) {
+        let location = location_key(chunk_location);
+        if self.tracker.mark_dirty(location, position, chunk_location) {
+            self.dispatch(position, chunk_location);
+        }
+    }
+
+    pub fn update(&mut self, world: &mut World, camera_position: cgmath::Vector3<f32>) {
+        self.pump(camera_position);
+
+        for _ in 0..self.max_completions_per_update {
+            let chunk = match self.receiver.try_recv() {
+                Ok(chunk) => chunk,
+                Err(_) => break,
+            };
+
             let pending = self.pending.remove(&chunk.idx).unwrap();
+            self.tracker.reset_building(pending.location);
+
             world.push((
                 Transform {
                     position: pending.position,
@@ -84,6 +266,10 @@ impl ChunkManager {
                 },
                 chunk,
             ));
+
+            if let Some((position, chunk_location)) = self.tracker.take_dirty(pending.location) {
+                self.dispatch(position, chunk_location);
+            }
         }
     }
 }
@@ -95,11 +281,15 @@ pub struct ChunkWorker {
 
 pub struct ChunkWorkerInitializer {
     device: Arc<wgpu::Device>,
+    buffer_return: Arc<Mutex<mpsc::Receiver<FreeBuffers>>>,
+    registry: Arc<BlockRegistry>,
 }
 
 pub struct ChunkWork {
     idx: u32,
-    position: cgmath::Vector2<f32>,
+    position: cgmath::Vector3<f32>,
+    camera_position: cgmath::Vector3<f32>,
+    snapshot: Snapshot,
     receiver: mpsc::Receiver<bool>,
     sender: mpsc::Sender<MeshReference>,
 }
@@ -112,6 +302,9 @@ impl Worker<ChunkWork, ChunkWorkerInitializer> for ChunkWorker {
     ) -> Self {
         let exector = ChunkExecutor {
             device: Arc::clone(&bundle.device),
+            buffer_return: Arc::clone(&bundle.buffer_return),
+            registry: Arc::clone(&bundle.registry),
+            free_buffers: RefCell::new(Vec::new()),
         };
         let thread = thread::spawn(move || loop {
             let work = receiver.lock().unwrap().recv().unwrap();
@@ -130,15 +323,67 @@ impl Worker<ChunkWork, ChunkWorkerInitializer> for ChunkWorker {
 
 pub struct ChunkExecutor {
     device: Arc<wgpu::Device>,
+    buffer_return: Arc<Mutex<mpsc::Receiver<FreeBuffers>>>,
+    registry: Arc<BlockRegistry>,
+    free_buffers: RefCell<Vec<FreeBuffers>>,
 }
 
 impl ChunkExecutor {
     fn execute(&self, data: ChunkWork) {
         let idx = data.idx;
-        let mesh = make_mesh(idx as u32, data.position);
+        while let Ok(returned) = self.buffer_return.lock().unwrap().try_recv() {
+            self.free_buffers.borrow_mut().push(returned);
+        }
+        let buffers = self
+            .free_buffers
+            .borrow_mut()
+            .pop()
+            .unwrap_or_else(FreeBuffers::new);
+        let (mesh, buffers) = make_mesh(
+            idx as u32,
+            data.snapshot,
+            data.position,
+            data.camera_position,
+            Arc::clone(&self.registry),
+            buffers,
+        );
+        self.free_buffers.borrow_mut().push(buffers);
         data.sender.send(mesh).unwrap();
     }
 }
+
+struct FreeBuffers {
+    vertices: Vec<MeshVertex>,
+    indices: Vec<u32>,
+    transparent_vertices: Vec<MeshVertex>,
+    transparent_indices: Vec<u32>,
+}
+
+impl FreeBuffers {
+    fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            transparent_vertices: Vec::new(),
+            transparent_indices: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.transparent_vertices.clear();
+        self.transparent_indices.clear();
+    }
+}
+
+/// Identifies a block's entry in the `BlockRegistry`. `AIR` is the sentinel
+/// for "no block" and is never rendered.
+pub type BlockId = u8;
+pub const AIR: BlockId = 0;
+const STONE: BlockId = 1;
+const GRASS: BlockId = 2;
+
 const CHUNK_SIZE: usize = 32;
 
 bitflags! {
@@ -164,61 +409,332 @@ const CUBE_COORDINATES: [[f32; 3]; 8] = [
     [-0.5, 0.5, -0.5],
 ];
 
-const UVS: [[f32; 2]; 4] = [
+const QUAD_UV_ORDER: [u32; 4] = [3, 2, 0, 1];
+
+/// The four UV corners of one atlas tile, row by row: (u min, v max),
+/// (u max, v max), (u min, v min), (u max, v min). Each quad vertex picks its
+/// corner via `QUAD_UV_ORDER`.
+pub type AtlasRect = [[f32; 2]; 4];
+
+/// `STONE`'s atlas rect, carried over unchanged from the single hardcoded
+/// texture window every block used before the registry existed.
+const STONE_FACES: AtlasRect = [
     [0.125, 1.0 - 0.9375],
     [0.1875, 1.0 - 0.9375],
     [0.125, 1.0 - 1.0],
     [0.1875, 1.0 - 1.0],
 ];
 
-const QUAD_UV_ORDER: [u32; 4] = [3, 2, 0, 1];
+const GRASS_TOP_FACE: AtlasRect = [
+    [0.1875, 1.0 - 0.9375],
+    [0.25, 1.0 - 0.9375],
+    [0.1875, 1.0 - 1.0],
+    [0.25, 1.0 - 1.0],
+];
+
+const GRASS_SIDE_FACE: AtlasRect = [
+    [0.25, 1.0 - 0.9375],
+    [0.3125, 1.0 - 0.9375],
+    [0.25, 1.0 - 1.0],
+    [0.3125, 1.0 - 1.0],
+];
+
+/// `GRASS`'s per-face split: green top, `GRASS_SIDE_FACE` on the four sides,
+/// and `STONE_FACES` as a stand-in dirt tile on the bottom — the case `BlockType::new`'s
+/// `[AtlasRect; 6]` exists for, ordered like `SIDE_VERTICES`.
+const GRASS_FACES: [AtlasRect; 6] = [
+    GRASS_TOP_FACE,
+    STONE_FACES,
+    GRASS_SIDE_FACE,
+    GRASS_SIDE_FACE,
+    GRASS_SIDE_FACE,
+    GRASS_SIDE_FACE,
+];
+
+/// Outward face normals, ordered to match `SIDE_VERTICES` (TOP, BOTTOM, LEFT,
+/// RIGHT, FORWARD, BACKWARD).
+const FACE_NORMALS: [[f32; 3]; 6] = [
+    [0.0, 1.0, 0.0],
+    [0.0, -1.0, 0.0],
+    [-1.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [0.0, 0.0, -1.0],
+];
+
+/// The (axis, sign) of each face's normal, ordered to match `SIDE_VERTICES`,
+/// used to walk into the voxel layer a face looks out onto when sampling
+/// ambient occlusion.
+const FACE_AXES: [(usize, i32); 6] = [(1, 1), (1, -1), (0, -1), (0, 1), (2, 1), (2, -1)];
+
+/// The two axes tangent to `axis`, used to locate the side/corner voxels an
+/// ambient occlusion sample checks.
+fn tangent_axes(axis: usize) -> (usize, usize) {
+    match axis {
+        0 => (1, 2),
+        1 => (0, 2),
+        _ => (0, 1),
+    }
+}
+
+/// A block's rendering data: the atlas rect per face (ordered like
+/// `SIDE_VERTICES`), a tint multiplied into its baked vertex color, and
+/// whether its faces belong in the transparent mesh pass.
+pub struct BlockType {
+    faces: [AtlasRect; 6],
+    tint: [f32; 3],
+    transparent: bool,
+}
+
+impl BlockType {
+    /// A block with a distinct atlas rect per face, e.g. grass's top/side/
+    /// bottom split.
+    fn new(faces: [AtlasRect; 6], tint: [f32; 3], transparent: bool) -> Self {
+        Self {
+            faces,
+            tint,
+            transparent,
+        }
+    }
+
+    /// A block that uses the same atlas rect on all six faces, like STONE.
+    fn uniform(face: AtlasRect, tint: [f32; 3], transparent: bool) -> Self {
+        Self::new([face; 6], tint, transparent)
+    }
+}
+
+/// Maps a block id to its `BlockType`. Shared across every chunk worker via
+/// `Arc` so the atlas layout is assembled once rather than per chunk.
+pub struct BlockRegistry {
+    blocks: Vec<BlockType>,
+}
+
+const WHITE_TINT: [f32; 3] = [1.0, 1.0, 1.0];
+
+impl BlockRegistry {
+    pub fn new() -> Self {
+        let mut blocks = Vec::new();
+        blocks.push(BlockType::uniform([[0.0, 0.0]; 4], WHITE_TINT, false)); // AIR, never rendered
+        blocks.push(BlockType::uniform(STONE_FACES, WHITE_TINT, false)); // STONE
+        blocks.push(BlockType::new(GRASS_FACES, WHITE_TINT, false)); // GRASS
+        debug_assert_eq!(blocks.len(), GRASS as usize + 1);
+        Self { blocks }
+    }
+
+    fn get(&self, id: BlockId) -> &BlockType {
+        &self.blocks[id as usize]
+    }
+}
 
 pub struct ChunkBuilder {
     idx: u32,
+    voxel_world: VoxelWorld,
+    registry: Arc<BlockRegistry>,
 }
 
 impl ChunkBuilder {
     pub fn new() -> Self {
-        Self { idx: 1 }
+        Self {
+            idx: 1,
+            voxel_world: VoxelWorld::new(),
+            registry: Arc::new(BlockRegistry::new()),
+        }
     }
 
-    pub fn make_mesh(&mut self, chunk_location: cgmath::Vector2<f32>) -> MeshReference {
-        let res = make_mesh(self.idx, chunk_location);
+    pub fn make_mesh(
+        &mut self,
+        position: cgmath::Vector3<f32>,
+        chunk_location: cgmath::Vector2<f32>,
+        camera_position: cgmath::Vector3<f32>,
+    ) -> MeshReference {
+        let snapshot = self.voxel_world.snapshot(chunk_location);
+        let (res, _) = make_mesh(
+            self.idx,
+            snapshot,
+            position,
+            camera_position,
+            Arc::clone(&self.registry),
+            FreeBuffers::new(),
+        );
         self.idx += 1;
         res
     }
 }
 
-pub fn make_mesh(idx: u32, chunk_location: cgmath::Vector2<f32>) -> MeshReference {
-    let mut builder = VoxelMeshBuilder::new();
-    let mut fbm = Fbm::new();
-    fbm.octaves = 4;
-    fbm.persistence = 0.5;
+/// Per-chunk `Section`s, generated from noise on first access and cached so
+/// a chunk's own snapshot and the borders of its neighbors' snapshots read
+/// the same data. A section is not modified after it is generated.
+pub struct VoxelWorld {
+    sections: Mutex<HashMap<ChunkLocation, Arc<Section>>>,
+}
+
+impl VoxelWorld {
+    pub fn new() -> Self {
+        Self {
+            sections: Mutex::new(HashMap::new()),
+        }
+    }
 
-    PlaneMapBuilder::new(&fbm).set_size(1000, 100);
-    let mut height_map = vec![vec![vec![false; CHUNK_SIZE + 2]; CHUNK_SIZE + 2]; CHUNK_SIZE + 2];
-    for x in 0..CHUNK_SIZE + 2 {
-        for z in 0..CHUNK_SIZE + 2 {
-            let stone_height = fbm.get([
-                (x as f32 + chunk_location.x) as f64 * 0.05,
-                (z as f32 + chunk_location.y) as f64 * 0.05,
-            ]) * 16.0
-                + (CHUNK_SIZE as f64 / 2.0);
+    fn section(&self, chunk_location: cgmath::Vector2<f32>) -> Arc<Section> {
+        let key = location_key(chunk_location);
+        let mut sections = self.sections.lock().unwrap();
+        Arc::clone(
+            sections
+                .entry(key)
+                .or_insert_with(|| Arc::new(Section::generate(chunk_location))),
+        )
+    }
+
+    /// Captures a padded snapshot of `chunk_location`'s section for meshing:
+    /// the section itself, bordered by one voxel pulled from each of its 8
+    /// horizontal neighbors (4 sides plus 4 diagonals — ambient occlusion's
+    /// corner samples land on the diagonal cells). There are no vertical
+    /// neighbor chunks, so the top and bottom border is always treated as
+    /// air.
+    pub fn snapshot(&self, chunk_location: cgmath::Vector2<f32>) -> Snapshot {
+        let step = CHUNK_SIZE as f32;
+        let center = self.section(chunk_location);
+        let west = self.section(chunk_location - cgmath::Vector2::new(step, 0.0));
+        let east = self.section(chunk_location + cgmath::Vector2::new(step, 0.0));
+        let north = self.section(chunk_location - cgmath::Vector2::new(0.0, step));
+        let south = self.section(chunk_location + cgmath::Vector2::new(0.0, step));
+        let northwest = self.section(chunk_location - cgmath::Vector2::new(step, step));
+        let northeast = self.section(chunk_location + cgmath::Vector2::new(step, -step));
+        let southwest = self.section(chunk_location + cgmath::Vector2::new(-step, step));
+        let southeast = self.section(chunk_location + cgmath::Vector2::new(step, step));
 
-            for y in 0..CHUNK_SIZE {
-                height_map[x][y][z] = y < stone_height as usize;
+        let mut voxels = vec![vec![vec![AIR; CHUNK_SIZE + 2]; CHUNK_SIZE + 2]; CHUNK_SIZE + 2];
+        for x in 0..CHUNK_SIZE + 2 {
+            for y in 0..CHUNK_SIZE + 2 {
+                for z in 0..CHUNK_SIZE + 2 {
+                    voxels[x][y][z] = Self::sample_border(
+                        &center, &west, &east, &north, &south, &northwest, &northeast, &southwest,
+                        &southeast, x, y, z,
+                    );
+                }
             }
         }
+        Snapshot { voxels }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn sample_border(
+        center: &Section,
+        west: &Section,
+        east: &Section,
+        north: &Section,
+        south: &Section,
+        northwest: &Section,
+        northeast: &Section,
+        southwest: &Section,
+        southeast: &Section,
+        x: usize,
+        y: usize,
+        z: usize,
+    ) -> BlockId {
+        if y == 0 || y == CHUNK_SIZE + 1 {
+            return AIR;
+        }
+        let iy = y - 1;
+        let in_x = x >= 1 && x <= CHUNK_SIZE;
+        let in_z = z >= 1 && z <= CHUNK_SIZE;
+
+        if in_x && in_z {
+            center.get(x - 1, iy, z - 1)
+        } else if x == 0 && in_z {
+            west.get(CHUNK_SIZE - 1, iy, z - 1)
+        } else if x == CHUNK_SIZE + 1 && in_z {
+            east.get(0, iy, z - 1)
+        } else if z == 0 && in_x {
+            north.get(x - 1, iy, CHUNK_SIZE - 1)
+        } else if z == CHUNK_SIZE + 1 && in_x {
+            south.get(x - 1, iy, 0)
+        } else if x == 0 && z == 0 {
+            northwest.get(CHUNK_SIZE - 1, iy, CHUNK_SIZE - 1)
+        } else if x == CHUNK_SIZE + 1 && z == 0 {
+            northeast.get(0, iy, CHUNK_SIZE - 1)
+        } else if x == 0 && z == CHUNK_SIZE + 1 {
+            southwest.get(CHUNK_SIZE - 1, iy, 0)
+        } else {
+            // x == CHUNK_SIZE + 1 && z == CHUNK_SIZE + 1, the only case left
+            // once in_x/in_z rule out everything else.
+            southeast.get(0, iy, 0)
+        }
+    }
+}
+
+/// One chunk's worth of voxel occupancy, indexed `[x][y][z]` in `0..CHUNK_SIZE`.
+struct Section {
+    voxels: Vec<Vec<Vec<BlockId>>>,
+}
+
+impl Section {
+    fn generate(chunk_location: cgmath::Vector2<f32>) -> Self {
+        let mut fbm = Fbm::new();
+        fbm.octaves = 4;
+        fbm.persistence = 0.5;
+
+        PlaneMapBuilder::new(&fbm).set_size(1000, 100);
+        let mut voxels = vec![vec![vec![AIR; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE];
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                let stone_height = fbm.get([
+                    (x as f32 + chunk_location.x) as f64 * 0.05,
+                    (z as f32 + chunk_location.y) as f64 * 0.05,
+                ]) * 16.0
+                    + (CHUNK_SIZE as f64 / 2.0);
+
+                for y in 0..CHUNK_SIZE {
+                    voxels[x][y][z] = if y < stone_height as usize {
+                        STONE
+                    } else {
+                        AIR
+                    };
+                }
+            }
+        }
+
+        Self { voxels }
+    }
+
+    fn get(&self, x: usize, y: usize, z: usize) -> BlockId {
+        self.voxels[x][y][z]
+    }
+}
+
+/// A chunk's padded occupancy grid, captured once at dispatch time: the
+/// chunk's own voxels (indices `1..=CHUNK_SIZE` per axis) plus one voxel of
+/// real neighbor data as a border, so meshing never needs to re-sample noise.
+pub struct Snapshot {
+    voxels: Vec<Vec<Vec<BlockId>>>,
+}
+
+impl Snapshot {
+    fn get(&self, x: usize, y: usize, z: usize) -> BlockId {
+        self.voxels[x][y][z]
+    }
+}
+
+pub fn make_mesh(
+    idx: u32,
+    snapshot: Snapshot,
+    position: cgmath::Vector3<f32>,
+    camera_position: cgmath::Vector3<f32>,
+    registry: Arc<BlockRegistry>,
+    buffers: FreeBuffers,
+) -> (MeshReference, FreeBuffers) {
+    let mut builder =
+        VoxelMeshBuilder::new(buffers, position, camera_position, registry, &snapshot);
+
     for x in 1..CHUNK_SIZE + 1 {
         for y in 1..CHUNK_SIZE + 1 {
             for z in 1..CHUNK_SIZE + 1 {
                 let pos = cgmath::Vector3::new(x, y, z);
-                if height_map[x][y][z] {
+                let block_id = snapshot.get(x, y, z);
+                if block_id != AIR {
                     builder
                         .set_position(&pos)
-                        .generate_voxel(get_sides(&height_map, &pos));
+                        .generate_voxel(get_sides(&snapshot, &pos), block_id);
                 }
             }
         }
@@ -227,41 +743,56 @@ pub fn make_mesh(idx: u32, chunk_location: cgmath::Vector2<f32>) -> MeshReferenc
     builder.build(idx)
 }
 
-fn get_sides(height_map: &Vec<Vec<Vec<bool>>>, pos: &cgmath::Vector3<usize>) -> Sides {
+fn get_sides(snapshot: &Snapshot, pos: &cgmath::Vector3<usize>) -> Sides {
     let mut sides = Sides::NONE;
 
-    if !(height_map[(pos.x - 1)][pos.y][pos.z]) {
+    if snapshot.get(pos.x - 1, pos.y, pos.z) == AIR {
         sides |= Sides::LEFT
     }
 
-    if !(height_map[(pos.x + 1)][pos.y][pos.z]) {
+    if snapshot.get(pos.x + 1, pos.y, pos.z) == AIR {
         sides |= Sides::RIGHT
     }
 
-    if (pos.y as i32) > 0 && !(height_map[(pos.x)][pos.y - 1][pos.z]) {
+    if (pos.y as i32) > 0 && snapshot.get(pos.x, pos.y - 1, pos.z) == AIR {
         sides |= Sides::BOTTOM
     }
 
-    if !(height_map[(pos.x)][pos.y + 1][pos.z]) {
+    if snapshot.get(pos.x, pos.y + 1, pos.z) == AIR {
         sides |= Sides::TOP
     }
 
-    if !(height_map[(pos.x)][pos.y][pos.z - 1]) {
+    if snapshot.get(pos.x, pos.y, pos.z - 1) == AIR {
         sides |= Sides::BACKWARD
     }
 
-    if !(height_map[(pos.x)][pos.y][pos.z + 1]) {
+    if snapshot.get(pos.x, pos.y, pos.z + 1) == AIR {
         sides |= Sides::FORWARD
     }
 
     sides
 }
 
-pub struct VoxelMeshBuilder {
+/// A transparent quad buffered until `build`, which sorts by camera distance
+/// before emitting indices, furthest first.
+struct TransparentQuad {
+    vertex_offset: u32,
+    centroid: cgmath::Vector3<f32>,
+    flip: bool,
+}
+
+pub struct VoxelMeshBuilder<'a> {
     current_cube_pos: cgmath::Vector3<u32>,
-    indices: Vec<u32>,
-    vertices: Vec<MeshVertex>,
-    index_offset: u32,
+    position: cgmath::Vector3<f32>,
+    camera_position: cgmath::Vector3<f32>,
+    registry: Arc<BlockRegistry>,
+    snapshot: &'a Snapshot,
+    solid_vertices: Vec<MeshVertex>,
+    solid_indices: Vec<u32>,
+    solid_index_offset: u32,
+    transparent_vertices: Vec<MeshVertex>,
+    transparent_indices: Vec<u32>,
+    transparent_quads: Vec<TransparentQuad>,
 }
 
 const SIDE_VERTICES: [(Sides, [u32; 4]); 6] = [
@@ -273,68 +804,479 @@ const SIDE_VERTICES: [(Sides, [u32; 4]); 6] = [
     (Sides::BACKWARD, [6, 7, 3, 2]),
 ];
 
-impl VoxelMeshBuilder {
-    pub fn new() -> Self {
+impl<'a> VoxelMeshBuilder<'a> {
+    pub fn new(
+        buffers: FreeBuffers,
+        position: cgmath::Vector3<f32>,
+        camera_position: cgmath::Vector3<f32>,
+        registry: Arc<BlockRegistry>,
+        snapshot: &'a Snapshot,
+    ) -> Self {
         Self {
             current_cube_pos: cgmath::Vector3::new(0, 0, 0),
-            indices: Vec::new(),
-            vertices: Vec::new(),
-            index_offset: 0,
+            position,
+            camera_position,
+            registry,
+            snapshot,
+            solid_vertices: buffers.vertices,
+            solid_indices: buffers.indices,
+            solid_index_offset: 0,
+            transparent_vertices: buffers.transparent_vertices,
+            transparent_indices: buffers.transparent_indices,
+            transparent_quads: Vec::new(),
         }
     }
 
-    pub fn set_position(&mut self, position: &cgmath::Vector3<usize>) -> &mut VoxelMeshBuilder {
+    pub fn set_position(&mut self, position: &cgmath::Vector3<usize>) -> &mut Self {
         self.current_cube_pos.x = position.x as u32;
         self.current_cube_pos.y = position.y as u32;
         self.current_cube_pos.z = position.z as u32;
         self
     }
 
-    pub fn move_position(&mut self, delta: cgmath::Vector3<u32>) -> &mut VoxelMeshBuilder {
+    pub fn move_position(&mut self, delta: cgmath::Vector3<u32>) -> &mut Self {
         self.current_cube_pos += delta;
         self
     }
 
-    pub fn generate_voxel(&mut self, sides: Sides) -> &mut VoxelMeshBuilder {
-        for (side, indices) in SIDE_VERTICES.iter() {
+    pub fn generate_voxel(&mut self, sides: Sides, block_id: BlockId) -> &mut Self {
+        let block = self.registry.get(block_id);
+        for (face_idx, (side, indices)) in SIDE_VERTICES.iter().enumerate() {
             if sides.contains(*side) {
-                self.build_quad(&indices);
+                let rect = &block.faces[face_idx];
+                let normal = FACE_NORMALS[face_idx];
+                let (axis, sign) = FACE_AXES[face_idx];
+                if block.transparent {
+                    self.build_transparent_quad(indices, rect, normal, block.tint, axis, sign);
+                } else {
+                    self.build_solid_quad(indices, rect, normal, block.tint, axis, sign);
+                }
             }
         }
         self
     }
 
-    pub fn build(self, idx: u32) -> MeshReference {
-        MeshReference {
+    pub fn build(mut self, idx: u32) -> (MeshReference, FreeBuffers) {
+        self.transparent_quads.sort_by(|a, b| {
+            let a_dist = (self.position + a.centroid - self.camera_position).magnitude2();
+            let b_dist = (self.position + b.centroid - self.camera_position).magnitude2();
+            b_dist
+                .partial_cmp(&a_dist)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        self.transparent_indices.clear();
+        for quad in &self.transparent_quads {
+            self.transparent_indices
+                .extend_from_slice(&quad_indices(quad.vertex_offset, quad.flip));
+        }
+
+        let mesh = MeshReference {
             idx,
-            vertex_data: self.vertices.into_boxed_slice(),
-            index_data: self.indices.into_boxed_slice(),
+            vertex_data: self.solid_vertices.as_slice().into(),
+            index_data: self.solid_indices.as_slice().into(),
+            transparent_vertex_data: self.transparent_vertices.as_slice().into(),
+            transparent_index_data: self.transparent_indices.as_slice().into(),
+        };
+
+        self.solid_vertices.clear();
+        self.solid_indices.clear();
+        self.transparent_vertices.clear();
+        self.transparent_indices.clear();
+
+        (
+            mesh,
+            FreeBuffers {
+                vertices: self.solid_vertices,
+                indices: self.solid_indices,
+                transparent_vertices: self.transparent_vertices,
+                transparent_indices: self.transparent_indices,
+            },
+        )
+    }
+
+    /// Ambient occlusion level (0, most occluded, to 3, unoccluded) for each
+    /// of `vertex_idx`'s corners, sampling the side/side/diagonal voxels the
+    /// face at (`axis`, `sign`) looks out onto.
+    fn quad_ao(&self, axis: usize, sign: i32, vertex_idx: &[u32; 4]) -> [f32; 4] {
+        let mut ao = [0.0; 4];
+        for (i, vertex) in vertex_idx.iter().enumerate() {
+            ao[i] = self.corner_ao(axis, sign, CUBE_COORDINATES[*vertex as usize]);
         }
+        ao
     }
 
-    fn build_quad(&mut self, vertex_idx: &[u32; 4]) {
-        for (vertex, uv) in vertex_idx.iter().zip(QUAD_UV_ORDER.iter()) {
-            let mut v = CUBE_COORDINATES[*vertex as usize].clone();
-            v[0] += self.current_cube_pos.x as f32;
-            v[1] += self.current_cube_pos.y as f32;
-            v[2] += self.current_cube_pos.z as f32;
-            self.vertices.push(MeshVertex {
-                position: v,
-                tex_coords: UVS[*uv as usize].clone(),
-                normal: [0.0, 1.0, 0.0],
-            });
+    fn corner_ao(&self, axis: usize, sign: i32, corner_offset: [f32; 3]) -> f32 {
+        let (t1, t2) = tangent_axes(axis);
+        let mut base = [
+            self.current_cube_pos.x as i32,
+            self.current_cube_pos.y as i32,
+            self.current_cube_pos.z as i32,
+        ];
+        base[axis] += sign;
+
+        let o1 = if corner_offset[t1] > 0.0 { 1 } else { -1 };
+        let o2 = if corner_offset[t2] > 0.0 { 1 } else { -1 };
+
+        let mut side1 = base;
+        side1[t1] += o1;
+        let mut side2 = base;
+        side2[t2] += o2;
+        let mut corner = base;
+        corner[t1] += o1;
+        corner[t2] += o2;
+
+        let occupied = |p: [i32; 3]| {
+            self.snapshot
+                .get(p[0] as usize, p[1] as usize, p[2] as usize)
+                != AIR
+        };
+
+        let side1_occ = occupied(side1);
+        let side2_occ = occupied(side2);
+        let corner_occ = occupied(corner);
+
+        if side1_occ && side2_occ {
+            0.0
+        } else {
+            3.0 - (side1_occ as i32 + side2_occ as i32 + corner_occ as i32) as f32
         }
-        self.indices.extend(
-            [
-                3 + self.index_offset,
-                1 + self.index_offset,
-                0 + self.index_offset,
-                3 + self.index_offset,
-                2 + self.index_offset,
-                1 + self.index_offset,
-            ]
-            .iter(),
+    }
+
+    fn quad_vertices(
+        &self,
+        vertex_idx: &[u32; 4],
+        rect: &AtlasRect,
+        normal: [f32; 3],
+        tint: [f32; 3],
+        ao: &[f32; 4],
+    ) -> Vec<MeshVertex> {
+        vertex_idx
+            .iter()
+            .zip(QUAD_UV_ORDER.iter())
+            .enumerate()
+            .map(|(i, (vertex, uv))| {
+                let mut v = CUBE_COORDINATES[*vertex as usize].clone();
+                v[0] += self.current_cube_pos.x as f32;
+                v[1] += self.current_cube_pos.y as f32;
+                v[2] += self.current_cube_pos.z as f32;
+                // Map the 0..3 occlusion level onto a brightness that never
+                // goes fully black, matching the usual voxel AO look.
+                let brightness = (ao[i] + 1.0) / 4.0;
+                MeshVertex {
+                    position: v,
+                    tex_coords: rect[*uv as usize].clone(),
+                    normal,
+                    color: [
+                        tint[0] * brightness,
+                        tint[1] * brightness,
+                        tint[2] * brightness,
+                    ],
+                }
+            })
+            .collect()
+    }
+
+    fn build_solid_quad(
+        &mut self,
+        vertex_idx: &[u32; 4],
+        rect: &AtlasRect,
+        normal: [f32; 3],
+        tint: [f32; 3],
+        axis: usize,
+        sign: i32,
+    ) {
+        let ao = self.quad_ao(axis, sign, vertex_idx);
+        self.solid_vertices
+            .extend(self.quad_vertices(vertex_idx, rect, normal, tint, &ao));
+        let o = self.solid_index_offset;
+        let flip = ao[1] + ao[3] > ao[0] + ao[2];
+        self.solid_indices.extend_from_slice(&quad_indices(o, flip));
+        self.solid_index_offset += 4;
+    }
+
+    fn build_transparent_quad(
+        &mut self,
+        vertex_idx: &[u32; 4],
+        rect: &AtlasRect,
+        normal: [f32; 3],
+        tint: [f32; 3],
+        axis: usize,
+        sign: i32,
+    ) {
+        let ao = self.quad_ao(axis, sign, vertex_idx);
+        let vertices = self.quad_vertices(vertex_idx, rect, normal, tint, &ao);
+        let vertex_offset = self.transparent_vertices.len() as u32;
+        let centroid = quad_centroid(&vertices);
+        let flip = ao[1] + ao[3] > ao[0] + ao[2];
+        self.transparent_vertices.extend(vertices);
+        self.transparent_quads.push(TransparentQuad {
+            vertex_offset,
+            centroid,
+            flip,
+        });
+    }
+}
+
+/// The two triangles of a quad, indexed relative to `offset`. `flip` picks
+/// which diagonal (0-2 or 1-3) splits the quad, avoiding the AO anisotropy
+/// artifact where the wrong diagonal makes shading interpolate unevenly.
+fn quad_indices(offset: u32, flip: bool) -> [u32; 6] {
+    if flip {
+        [
+            0 + offset,
+            2 + offset,
+            1 + offset,
+            0 + offset,
+            3 + offset,
+            2 + offset,
+        ]
+    } else {
+        [
+            3 + offset,
+            1 + offset,
+            0 + offset,
+            3 + offset,
+            2 + offset,
+            1 + offset,
+        ]
+    }
+}
+
+fn quad_centroid(vertices: &[MeshVertex]) -> cgmath::Vector3<f32> {
+    let sum = vertices
+        .iter()
+        .fold(cgmath::Vector3::new(0.0, 0.0, 0.0), |acc, v| {
+            acc + cgmath::Vector3::new(v.position[0], v.position[1], v.position[2])
+        });
+    sum / vertices.len() as f32
+}
+
+#[cfg(test)]
+mod build_tracker_tests {
+    use super::*;
+
+    fn pos(x: f32) -> (cgmath::Vector3<f32>, cgmath::Vector2<f32>) {
+        (
+            cgmath::Vector3::new(x, 0.0, 0.0),
+            cgmath::Vector2::new(x, 0.0),
+        )
+    }
+
+    #[test]
+    fn mark_dirty_dispatches_immediately_when_not_building() {
+        let mut tracker = BuildTracker::new();
+        let (position, chunk_location) = pos(0.0);
+        assert!(tracker.mark_dirty((0, 0), position, chunk_location));
+        assert!(tracker.take_dirty((0, 0)).is_none());
+    }
+
+    #[test]
+    fn mark_dirty_defers_while_building() {
+        let mut tracker = BuildTracker::new();
+        tracker.set_building((0, 0));
+        let (position, chunk_location) = pos(1.0);
+
+        assert!(!tracker.mark_dirty((0, 0), position, chunk_location));
+        assert_eq!(tracker.take_dirty((0, 0)), Some((position, chunk_location)));
+        // Taking it clears the deferral.
+        assert!(tracker.take_dirty((0, 0)).is_none());
+    }
+
+    #[test]
+    fn reset_building_allows_next_dispatch() {
+        let mut tracker = BuildTracker::new();
+        tracker.set_building((0, 0));
+        assert!(tracker.is_building((0, 0)));
+        assert_eq!(tracker.in_flight(), 1);
+
+        tracker.reset_building((0, 0));
+        assert!(!tracker.is_building((0, 0)));
+        assert_eq!(tracker.in_flight(), 0);
+    }
+}
+
+#[cfg(test)]
+mod candidate_sort_tests {
+    use super::*;
+
+    fn candidate_at(x: f32) -> Candidate {
+        Candidate {
+            position: cgmath::Vector3::new(x, 0.0, 0.0),
+            chunk_location: cgmath::Vector2::new(x, 0.0),
+            location: (x as i32, 0),
+        }
+    }
+
+    #[test]
+    fn pops_nearest_candidate_first() {
+        let mut queue = vec![candidate_at(10.0), candidate_at(1.0), candidate_at(5.0)];
+        sort_candidates_farthest_first(&mut queue, cgmath::Vector3::new(0.0, 0.0, 0.0));
+
+        assert_eq!(queue.pop().unwrap().location, (1, 0));
+        assert_eq!(queue.pop().unwrap().location, (5, 0));
+        assert_eq!(queue.pop().unwrap().location, (10, 0));
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn orders_relative_to_camera_position() {
+        let mut queue = vec![candidate_at(0.0), candidate_at(10.0)];
+        sort_candidates_farthest_first(&mut queue, cgmath::Vector3::new(10.0, 0.0, 0.0));
+
+        // Nearest the camera (x = 10) should pop first.
+        assert_eq!(queue.pop().unwrap().location, (10, 0));
+        assert_eq!(queue.pop().unwrap().location, (0, 0));
+    }
+}
+
+#[cfg(test)]
+mod quad_indices_tests {
+    use super::*;
+
+    #[test]
+    fn unflipped_splits_along_1_3_diagonal() {
+        assert_eq!(quad_indices(0, false), [3, 1, 0, 3, 2, 1]);
+    }
+
+    #[test]
+    fn flipped_splits_along_0_2_diagonal() {
+        assert_eq!(quad_indices(0, true), [0, 2, 1, 0, 3, 2]);
+    }
+
+    #[test]
+    fn both_windings_cover_all_four_corners_once_per_triangle_pair() {
+        for flip in [false, true] {
+            let indices = quad_indices(0, flip);
+            let mut corners: Vec<u32> = indices.to_vec();
+            corners.sort();
+            corners.dedup();
+            assert_eq!(corners, vec![0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn offset_shifts_every_index() {
+        let base = quad_indices(0, false);
+        let shifted = quad_indices(4, false);
+        for (b, s) in base.iter().zip(shifted.iter()) {
+            assert_eq!(*s, *b + 4);
+        }
+    }
+}
+
+#[cfg(test)]
+mod ambient_occlusion_tests {
+    use super::*;
+
+    fn blank_snapshot() -> Snapshot {
+        Snapshot {
+            voxels: vec![vec![vec![AIR; CHUNK_SIZE + 2]; CHUNK_SIZE + 2]; CHUNK_SIZE + 2],
+        }
+    }
+
+    fn builder(snapshot: &Snapshot) -> VoxelMeshBuilder {
+        VoxelMeshBuilder::new(
+            FreeBuffers::new(),
+            cgmath::Vector3::new(0.0, 0.0, 0.0),
+            cgmath::Vector3::new(0.0, 0.0, 0.0),
+            Arc::new(BlockRegistry::new()),
+            snapshot,
+        )
+    }
+
+    // All of these probe the TOP face (axis 1, sign 1) of the voxel at
+    // (5, 5, 5), whose tangent axes are x and z (`tangent_axes(1) == (0, 2)`).
+
+    #[test]
+    fn corner_ao_is_unoccluded_with_no_neighbors() {
+        let snapshot = blank_snapshot();
+        let mut b = builder(&snapshot);
+        b.set_position(&cgmath::Vector3::new(5, 5, 5));
+        assert_eq!(b.corner_ao(1, 1, CUBE_COORDINATES[7]), 3.0);
+    }
+
+    #[test]
+    fn corner_ao_is_fully_occluded_when_both_sides_are_occupied() {
+        let mut snapshot = blank_snapshot();
+        snapshot.voxels[4][6][5] = STONE; // side toward -x
+        snapshot.voxels[5][6][4] = STONE; // side toward -z
+        let mut b = builder(&snapshot);
+        b.set_position(&cgmath::Vector3::new(5, 5, 5));
+        assert_eq!(b.corner_ao(1, 1, CUBE_COORDINATES[7]), 0.0);
+    }
+
+    #[test]
+    fn corner_ao_is_partially_occluded_by_the_diagonal_alone() {
+        let mut snapshot = blank_snapshot();
+        snapshot.voxels[4][6][4] = STONE; // diagonal only, no sides occupied
+        let mut b = builder(&snapshot);
+        b.set_position(&cgmath::Vector3::new(5, 5, 5));
+        assert_eq!(b.corner_ao(1, 1, CUBE_COORDINATES[7]), 2.0);
+    }
+
+    #[test]
+    fn corner_ao_reads_real_data_across_the_padded_border() {
+        // Checks that `corner_ao` reads a snapshot's padding cells: the
+        // (0, 0, 0) corner cell is set by hand here. This does not exercise
+        // `VoxelWorld::snapshot`, which leaves its whole y = 0 border as AIR.
+        let mut snapshot = blank_snapshot();
+        snapshot.voxels[0][0][0] = STONE;
+        let mut b = builder(&snapshot);
+        b.set_position(&cgmath::Vector3::new(1, 1, 1));
+        // LEFT face (axis 0, sign -1) of the voxel just inside the border;
+        // its corner sample lands on the snapshot's x=0, z=0 cell.
+        assert_eq!(b.corner_ao(0, -1, CUBE_COORDINATES[3]), 2.0);
+    }
+
+    #[test]
+    fn quad_ao_reports_a_level_per_corner() {
+        let mut snapshot = blank_snapshot();
+        snapshot.voxels[4][6][5] = STONE;
+        snapshot.voxels[5][6][4] = STONE;
+        let mut b = builder(&snapshot);
+        b.set_position(&cgmath::Vector3::new(5, 5, 5));
+        let (_, vertex_idx) = SIDE_VERTICES[0]; // TOP: [7, 6, 5, 4]
+        assert_eq!(b.quad_ao(1, 1, &vertex_idx), [0.0, 2.0, 3.0, 2.0]);
+    }
+}
+
+#[cfg(test)]
+mod transparent_sort_tests {
+    use super::*;
+
+    #[test]
+    fn build_sorts_transparent_quads_farthest_from_camera_first() {
+        let snapshot = Snapshot {
+            voxels: vec![vec![vec![AIR; CHUNK_SIZE + 2]; CHUNK_SIZE + 2]; CHUNK_SIZE + 2],
+        };
+        let mut builder = VoxelMeshBuilder::new(
+            FreeBuffers::new(),
+            cgmath::Vector3::new(0.0, 0.0, 0.0),
+            cgmath::Vector3::new(100.0, 0.0, 0.0),
+            Arc::new(BlockRegistry::new()),
+            &snapshot,
         );
-        self.index_offset += 4;
+        // Nearer the camera (world x = 50, distance 50) than the other quad
+        // (world x = 1, distance 99) — but closer to `position` (world
+        // origin), which is what the pre-49c2af0 sort mistakenly used.
+        builder.transparent_quads = vec![
+            TransparentQuad {
+                vertex_offset: 4,
+                centroid: cgmath::Vector3::new(50.0, 0.0, 0.0),
+                flip: true,
+            },
+            TransparentQuad {
+                vertex_offset: 0,
+                centroid: cgmath::Vector3::new(1.0, 0.0, 0.0),
+                flip: false,
+            },
+        ];
+
+        let (mesh, _) = builder.build(1);
+
+        let mut expected = quad_indices(0, false).to_vec();
+        expected.extend_from_slice(&quad_indices(4, true));
+        assert_eq!(mesh.transparent_index_data.to_vec(), expected);
     }
 }